@@ -1,6 +1,7 @@
 use std::error::Error;
 
 use backend::{vulkan, Backend};
+use log::error;
 use winit::dpi::LogicalSize;
 use winit::event::{Event, WindowEvent};
 use winit::event_loop::EventLoop;
@@ -9,26 +10,36 @@ use winit::window::WindowBuilder;
 
 use w_gfx::*;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    pretty_env_logger::init();
+fn run(
+    mut backend: impl Backend,
+    window: winit::window::Window,
+    event_loop: EventLoop<()>,
+) -> Result<(), Box<dyn Error>> {
+    backend.create_surface(window.display_handle()?, window.window_handle()?)?;
 
-    let event_loop = EventLoop::new()?;
-    let window = WindowBuilder::new()
-        .with_title("Vulkan Tutorial (Rust)")
-        .with_inner_size(LogicalSize::new(1024, 768))
-        .build(&event_loop)?;
-
-    let mut vulkan = vulkan::Vulkan::new(window.display_handle()?)?;
-    vulkan.create_surface(window.display_handle()?, window.window_handle()?)?;
-    vulkan.create_device()?;
+    let size = window.inner_size();
+    backend.create_device(size.width, size.height)?;
 
     event_loop.run(move |event, elwt| match event {
         Event::AboutToWait => window.request_redraw(),
         Event::WindowEvent { event, .. } => match event {
-            WindowEvent::RedrawRequested if !elwt.exiting() => (),
+            WindowEvent::RedrawRequested if !elwt.exiting() => {
+                if let Err(error) = backend.render() {
+                    error!("{}", error);
+                    elwt.exit();
+                    backend.destroy();
+                }
+            }
+            WindowEvent::Resized(size) => {
+                if let Err(error) = backend.resize(size.width, size.height) {
+                    error!("{}", error);
+                    elwt.exit();
+                    backend.destroy();
+                }
+            }
             WindowEvent::CloseRequested => {
                 elwt.exit();
-                vulkan.destroy();
+                backend.destroy();
             }
             _ => {}
         },
@@ -37,3 +48,17 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+fn main() -> Result<(), Box<dyn Error>> {
+    pretty_env_logger::init();
+
+    let event_loop = EventLoop::new()?;
+    let window = WindowBuilder::new()
+        .with_title("Vulkan Tutorial (Rust)")
+        .with_inner_size(LogicalSize::new(1024, 768))
+        .build(&event_loop)?;
+
+    let vulkan = vulkan::Vulkan::new(window.display_handle()?)?;
+
+    run(vulkan, window, event_loop)
+}
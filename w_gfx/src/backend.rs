@@ -6,6 +6,21 @@ pub mod vulkan;
 pub trait Backend: Sized {
     fn new(display_handle: DisplayHandle) -> Result<Self, Error>;
     fn destroy(&mut self);
+
+    fn create_surface(
+        &mut self,
+        display_handle: DisplayHandle,
+        window_handle: WindowHandle,
+    ) -> Result<(), Error>;
+    /// Creates the logical device and the initial swapchain sized for `width`/`height`
+    /// (the window's size at the time the surface was created).
+    fn create_device(&mut self, width: u32, height: u32) -> Result<(), Error>;
+
+    /// Renders and presents a single frame.
+    fn render(&mut self) -> Result<(), Error>;
+    /// Notifies the backend that the surface has been resized, so it can recreate
+    /// whatever size-dependent state (e.g. a swapchain) it owns.
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), Error>;
 }
 
 #[derive(Debug)]
@@ -9,9 +9,57 @@ use raw_window_handle::RawDisplayHandle;
 
 const PORTABILITY_MACOS_VERSION: u32 = vk::make_api_version(0, 1, 3, 216);
 
+const REQUIRED_DEVICE_EXTENSIONS: &[&CStr] = &[khr::swapchain::NAME];
+
 const VALIDATION_ENABLED: bool = cfg!(debug_assertions);
 const VALIDATION_LAYER_NAME: &CStr = c"VK_LAYER_KHRONOS_validation";
 
+/// A known-false-positive `VUID-vkCmdEndDebugUtilsLabelEXT-commandBuffer-01912`, only emitted by
+/// Khronos validation layers in the `1.3.240..=1.3.250` spec-version range.
+const VUID_END_DEBUG_LABEL_SPURIOUS: i32 = 0x5614_6426_u32 as i32;
+const VUID_END_DEBUG_LABEL_SPURIOUS_LAYER_VERSIONS: std::ops::RangeInclusive<u32> =
+    vk::make_api_version(0, 1, 3, 240)..=vk::make_api_version(0, 1, 3, 250);
+
+/// Spurious swapchain-extent validation noise, unconditionally silenced.
+const VUID_SWAPCHAIN_EXTENT_SPURIOUS: i32 = 0x7cd0_911d_u32 as i32;
+
+/// Context handed to [`debug_callback`] via `p_user_data`, so it can tell a real validation
+/// error apart from layer-version-specific false positives.
+struct DebugUtilsMessengerUserData {
+    validation_layer_spec_version: u32,
+    suppressed_message_ids: HashSet<i32>,
+}
+
+impl DebugUtilsMessengerUserData {
+    fn new(validation_layer_spec_version: u32) -> Self {
+        let mut suppressed_message_ids = HashSet::new();
+        suppressed_message_ids.insert(VUID_SWAPCHAIN_EXTENT_SPURIOUS);
+
+        if VUID_END_DEBUG_LABEL_SPURIOUS_LAYER_VERSIONS.contains(&validation_layer_spec_version) {
+            suppressed_message_ids.insert(VUID_END_DEBUG_LABEL_SPURIOUS);
+        }
+
+        Self {
+            validation_layer_spec_version,
+            suppressed_message_ids,
+        }
+    }
+
+    fn should_suppress(&self, message_id_number: i32) -> bool {
+        let suppressed = self.suppressed_message_ids.contains(&message_id_number);
+
+        if suppressed {
+            trace!(
+                "Suppressing known-spurious validation message {:#x} (validation layer {})",
+                message_id_number,
+                self.validation_layer_spec_version
+            );
+        }
+
+        suppressed
+    }
+}
+
 impl Error {
     fn new(msg: String) -> Self {
         Self {
@@ -30,27 +78,44 @@ pub struct Vulkan {
     entry: Entry,
     instance: Instance,
     debug_utils_messenger: Option<vk::DebugUtilsMessengerEXT>,
+    debug_utils_user_data: Option<Box<DebugUtilsMessengerUserData>>,
     surface: Option<vk::SurfaceKHR>,
     device: Option<Device>,
     physical_device: Option<vk::PhysicalDevice>,
     graphics_queue: Option<vk::Queue>,
     present_queue: Option<vk::Queue>,
+    transfer_queue: Option<vk::Queue>,
+    compute_queue: Option<vk::Queue>,
+    swapchain: Option<vk::SwapchainKHR>,
+    swapchain_format: vk::Format,
+    swapchain_extent: vk::Extent2D,
+    swapchain_images: Vec<vk::Image>,
+    swapchain_image_views: Vec<vk::ImageView>,
 }
 
 impl Backend for Vulkan {
     fn new(display_handle: DisplayHandle) -> Result<Self, super::Error> {
         let entry = Entry::linked();
-        let (instance, debug_utils_messenger) = create_instance(display_handle.as_raw(), &entry)?;
+        let (instance, debug_utils_messenger, debug_utils_user_data) =
+            create_instance(display_handle.as_raw(), &entry)?;
 
         Ok(Self {
             entry,
             instance,
             debug_utils_messenger,
+            debug_utils_user_data,
             surface: None,
             device: None,
             physical_device: None,
             graphics_queue: None,
             present_queue: None,
+            transfer_queue: None,
+            compute_queue: None,
+            swapchain: None,
+            swapchain_format: vk::Format::UNDEFINED,
+            swapchain_extent: vk::Extent2D::default(),
+            swapchain_images: Vec::new(),
+            swapchain_image_views: Vec::new(),
         })
     }
 
@@ -62,6 +127,8 @@ impl Backend for Vulkan {
             }
         }
 
+        self.destroy_swapchain();
+
         if let Some(device) = &self.device {
             unsafe {
                 device.destroy_device(None);
@@ -79,10 +146,8 @@ impl Backend for Vulkan {
             self.instance.destroy_instance(None);
         }
     }
-}
 
-impl Vulkan {
-    pub fn create_surface(
+    fn create_surface(
         &mut self,
         display_handle: DisplayHandle,
         window_handle: WindowHandle,
@@ -100,7 +165,7 @@ impl Vulkan {
         Ok(())
     }
 
-    pub fn create_device(&mut self) -> Result<(), Error> {
+    fn create_device(&mut self, width: u32, height: u32) -> Result<(), Error> {
         let surface = self.surface.ok_or(Error::new(
             "Can't create device without a surface".to_string(),
         ))?;
@@ -112,12 +177,9 @@ impl Vulkan {
         let indices =
             QueueFamilyIndices::get(&self.entry, &self.instance, surface, physical_device).unwrap();
 
-        let mut unique_indices = HashSet::new();
-        unique_indices.insert(indices.graphics);
-        unique_indices.insert(indices.present);
-
         let queue_priorities = &[1.0];
-        let queue_infos = unique_indices
+        let queue_infos = indices
+            .unique_indices()
             .iter()
             .map(|i| vk::DeviceQueueCreateInfo {
                 queue_family_index: *i,
@@ -127,7 +189,10 @@ impl Vulkan {
             })
             .collect::<Vec<_>>();
 
-        let mut extensions = vec![];
+        let mut extensions = REQUIRED_DEVICE_EXTENSIONS
+            .iter()
+            .map(|e| e.as_ptr())
+            .collect::<Vec<_>>();
 
         // required for mac since 1.3.216
         if cfg!(target_os = "macos") && instance_version >= PORTABILITY_MACOS_VERSION {
@@ -153,19 +218,232 @@ impl Vulkan {
         unsafe {
             self.graphics_queue = Some(device.get_device_queue(indices.graphics, 0));
             self.present_queue = Some(device.get_device_queue(indices.present, 0));
+            self.transfer_queue = Some(device.get_device_queue(indices.transfer, 0));
+            self.compute_queue = Some(device.get_device_queue(indices.compute, 0));
         }
 
         self.device = Some(device);
         self.physical_device = Some(physical_device);
 
+        self.create_swapchain(width, height)
+    }
+
+    fn render(&mut self) -> Result<(), Error> {
+        // No command buffers or frame-sync primitives exist yet, so there's nothing to
+        // submit or present; this is the hook the event loop now calls every frame.
         Ok(())
     }
+
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        if self.device.is_none() {
+            return Ok(());
+        }
+
+        // The window reports a 0x0 size while minimized on several platforms; surface
+        // capabilities follow suit in that state, so recreating here would hand
+        // `vkCreateSwapchainKHR` a zero extent. Leave the existing swapchain in place
+        // until a real size comes back.
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+
+        self.destroy_swapchain();
+        self.create_swapchain(width, height)
+    }
+}
+
+impl Vulkan {
+    fn destroy_swapchain(&mut self) {
+        if let Some(device) = &self.device {
+            unsafe {
+                for image_view in self.swapchain_image_views.drain(..) {
+                    device.destroy_image_view(image_view, None);
+                }
+
+                if let Some(swapchain) = self.swapchain.take() {
+                    khr::swapchain::Device::new(&self.instance, device)
+                        .destroy_swapchain(swapchain, None);
+                }
+            }
+        }
+
+        self.swapchain_images.clear();
+    }
+
+    pub fn create_swapchain(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        let surface = self.surface.ok_or(Error::new(
+            "Can't create swapchain without a surface".to_string(),
+        ))?;
+        let physical_device = self.physical_device.ok_or(Error::new(
+            "Can't create swapchain without a physical device".to_string(),
+        ))?;
+        let device = self.device.as_ref().ok_or(Error::new(
+            "Can't create swapchain without a logical device".to_string(),
+        ))?;
+
+        let indices =
+            QueueFamilyIndices::get(&self.entry, &self.instance, surface, physical_device)
+                .map_err(|e| Error::new(e.to_string()))?;
+        let support = unsafe {
+            SwapchainSupport::get(&self.entry, &self.instance, surface, physical_device)
+        }?;
+
+        let surface_format = support.pick_surface_format();
+        let present_mode = support.pick_present_mode();
+        let extent = support.pick_extent(width, height);
+
+        let mut image_count = support.capabilities.min_image_count + 1;
+        if support.capabilities.max_image_count != 0
+            && image_count > support.capabilities.max_image_count
+        {
+            image_count = support.capabilities.max_image_count;
+        }
+
+        let mut queue_family_indices = vec![];
+        let (image_sharing_mode, queue_family_index_count, p_queue_family_indices) =
+            if indices.graphics != indices.present {
+                queue_family_indices.push(indices.graphics);
+                queue_family_indices.push(indices.present);
+                (
+                    vk::SharingMode::CONCURRENT,
+                    queue_family_indices.len() as u32,
+                    queue_family_indices.as_ptr(),
+                )
+            } else {
+                (vk::SharingMode::EXCLUSIVE, 0, std::ptr::null())
+            };
+
+        let create_info = vk::SwapchainCreateInfoKHR {
+            surface,
+            min_image_count: image_count,
+            image_format: surface_format.format,
+            image_color_space: surface_format.color_space,
+            image_extent: extent,
+            image_array_layers: 1,
+            image_usage: vk::ImageUsageFlags::COLOR_ATTACHMENT,
+            image_sharing_mode,
+            queue_family_index_count,
+            p_queue_family_indices,
+            pre_transform: support.capabilities.current_transform,
+            composite_alpha: vk::CompositeAlphaFlagsKHR::OPAQUE,
+            present_mode,
+            clipped: vk::TRUE,
+            old_swapchain: vk::SwapchainKHR::null(),
+            ..Default::default()
+        };
+
+        let swapchain_loader = khr::swapchain::Device::new(&self.instance, device);
+        let swapchain = unsafe { swapchain_loader.create_swapchain(&create_info, None)? };
+        let images = unsafe { swapchain_loader.get_swapchain_images(swapchain)? };
+
+        let image_views = images
+            .iter()
+            .map(|image| {
+                let create_info = vk::ImageViewCreateInfo {
+                    image: *image,
+                    view_type: vk::ImageViewType::TYPE_2D,
+                    format: surface_format.format,
+                    components: vk::ComponentMapping::default(),
+                    subresource_range: vk::ImageSubresourceRange {
+                        aspect_mask: vk::ImageAspectFlags::COLOR,
+                        base_mip_level: 0,
+                        level_count: 1,
+                        base_array_layer: 0,
+                        layer_count: 1,
+                    },
+                    ..Default::default()
+                };
+
+                unsafe { device.create_image_view(&create_info, None) }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.swapchain = Some(swapchain);
+        self.swapchain_format = surface_format.format;
+        self.swapchain_extent = extent;
+        self.swapchain_images = images;
+        self.swapchain_image_views = image_views;
+
+        Ok(())
+    }
+}
+
+struct SwapchainSupport {
+    capabilities: vk::SurfaceCapabilitiesKHR,
+    formats: Vec<vk::SurfaceFormatKHR>,
+    present_modes: Vec<vk::PresentModeKHR>,
+}
+
+impl SwapchainSupport {
+    unsafe fn get(
+        entry: &Entry,
+        instance: &Instance,
+        surface: vk::SurfaceKHR,
+        physical_device: vk::PhysicalDevice,
+    ) -> Result<Self, Error> {
+        let surface_loader = khr::surface::Instance::new(entry, instance);
+
+        Ok(Self {
+            capabilities: unsafe {
+                surface_loader.get_physical_device_surface_capabilities(physical_device, surface)?
+            },
+            formats: unsafe {
+                surface_loader.get_physical_device_surface_formats(physical_device, surface)?
+            },
+            present_modes: unsafe {
+                surface_loader
+                    .get_physical_device_surface_present_modes(physical_device, surface)?
+            },
+        })
+    }
+
+    fn pick_surface_format(&self) -> vk::SurfaceFormatKHR {
+        self.formats
+            .iter()
+            .find(|f| {
+                f.format == vk::Format::B8G8R8A8_SRGB
+                    && f.color_space == vk::ColorSpaceKHR::SRGB_NONLINEAR
+            })
+            .copied()
+            .unwrap_or(self.formats[0])
+    }
+
+    fn pick_present_mode(&self) -> vk::PresentModeKHR {
+        self.present_modes
+            .iter()
+            .find(|m| **m == vk::PresentModeKHR::MAILBOX)
+            .copied()
+            .unwrap_or(vk::PresentModeKHR::FIFO)
+    }
+
+    fn pick_extent(&self, width: u32, height: u32) -> vk::Extent2D {
+        if self.capabilities.current_extent.width != u32::MAX {
+            self.capabilities.current_extent
+        } else {
+            vk::Extent2D {
+                width: width.clamp(
+                    self.capabilities.min_image_extent.width,
+                    self.capabilities.max_image_extent.width,
+                ),
+                height: height.clamp(
+                    self.capabilities.min_image_extent.height,
+                    self.capabilities.max_image_extent.height,
+                ),
+            }
+        }
+    }
 }
 
+type CreateInstanceResult = (
+    Instance,
+    Option<vk::DebugUtilsMessengerEXT>,
+    Option<Box<DebugUtilsMessengerUserData>>,
+);
+
 pub fn create_instance(
     rdh: RawDisplayHandle,
     entry: &Entry,
-) -> Result<(Instance, Option<vk::DebugUtilsMessengerEXT>), super::Error> {
+) -> Result<CreateInstanceResult, super::Error> {
     let instance_version = unsafe {
         entry
             .try_enumerate_instance_version()?
@@ -210,6 +488,14 @@ pub fn create_instance(
         ));
     }
 
+    let validation_layer_spec_version = instance_layer_properties
+        .iter()
+        .find(|l| {
+            l.layer_name_as_c_str().expect("Invalid vulkan layer name.") == VALIDATION_LAYER_NAME
+        })
+        .map(|l| l.spec_version)
+        .unwrap_or(0);
+
     let layers = if VALIDATION_ENABLED {
         info!("Enabling validation layers");
         vec![VALIDATION_LAYER_NAME.as_ptr()]
@@ -227,6 +513,16 @@ pub fn create_instance(
         ..Default::default()
     };
 
+    let user_data = VALIDATION_ENABLED.then(|| {
+        Box::new(DebugUtilsMessengerUserData::new(
+            validation_layer_spec_version,
+        ))
+    });
+    let p_user_data = user_data
+        .as_ref()
+        .map(|data| data.as_ref() as *const DebugUtilsMessengerUserData as *mut c_void)
+        .unwrap_or(std::ptr::null_mut());
+
     let mut debug_info = vk::DebugUtilsMessengerCreateInfoEXT {
         message_severity: vk::DebugUtilsMessageSeverityFlagsEXT::ERROR
             | vk::DebugUtilsMessageSeverityFlagsEXT::WARNING
@@ -236,6 +532,7 @@ pub fn create_instance(
             | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION
             | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE,
         pfn_user_callback: Some(debug_callback),
+        p_user_data,
         ..Default::default()
     };
 
@@ -251,13 +548,15 @@ pub fn create_instance(
         None
     };
 
-    Ok((instance, debug_utils_messenger))
+    Ok((instance, debug_utils_messenger, user_data))
 }
 
 #[derive(Copy, Clone, Debug)]
 struct QueueFamilyIndices {
     graphics: u32,
     present: u32,
+    transfer: u32,
+    compute: u32,
 }
 
 impl QueueFamilyIndices {
@@ -288,11 +587,42 @@ impl QueueFamilyIndices {
         }
 
         if let (Some(graphics), Some(present)) = (graphics, present) {
-            Ok(Self { graphics, present })
+            // Prefer a dedicated transfer family (transfer-capable, not also graphics) so
+            // staging-buffer uploads don't serialize behind graphics work; fall back to
+            // sharing the graphics family when the device has no such family.
+            let transfer = properties
+                .iter()
+                .position(|p| {
+                    p.queue_flags.contains(vk::QueueFlags::TRANSFER)
+                        && !p.queue_flags.contains(vk::QueueFlags::GRAPHICS)
+                })
+                .map(|i| i as u32)
+                .unwrap_or(graphics);
+
+            // Same idea for async compute: prefer a compute-only family, else share graphics.
+            let compute = properties
+                .iter()
+                .position(|p| {
+                    p.queue_flags.contains(vk::QueueFlags::COMPUTE)
+                        && !p.queue_flags.contains(vk::QueueFlags::GRAPHICS)
+                })
+                .map(|i| i as u32)
+                .unwrap_or(graphics);
+
+            Ok(Self {
+                graphics,
+                present,
+                transfer,
+                compute,
+            })
         } else {
             Err(SuitabilityError("Missing required queue families"))
         }
     }
+
+    fn unique_indices(&self) -> HashSet<u32> {
+        HashSet::from([self.graphics, self.present, self.transfer, self.compute])
+    }
 }
 
 #[derive(Debug)]
@@ -310,23 +640,53 @@ pub fn pick_physical_device(
     instance: &Instance,
     surface: vk::SurfaceKHR,
 ) -> Result<vk::PhysicalDevice, super::Error> {
+    let mut best = None;
+
     for physical_device in unsafe { instance.enumerate_physical_devices()? } {
         let properties = unsafe { instance.get_physical_device_properties(physical_device) };
 
         let device_name =
             unsafe { CStr::from_ptr(properties.device_name.as_ptr()) }.to_string_lossy();
 
-        if let Err(error) =
+        let score = if let Err(error) =
             unsafe { check_physical_device(entry, instance, surface, physical_device) }
         {
             warn!("Skipping physical device '{}': {}", device_name, error);
+            0
         } else {
-            info!("Selected physical device '{}'.", device_name);
-            return Ok(physical_device);
+            score_physical_device(&properties)
+        };
+
+        info!(
+            "Candidate physical device '{}' scored {}.",
+            device_name, score
+        );
+
+        let is_better = match &best {
+            Some((best_score, _)) => score > *best_score,
+            None => true,
+        };
+        if score > 0 && is_better {
+            best = Some((score, physical_device));
         }
     }
 
-    Err(Error::new("No suitable physical device".to_string()))
+    match best {
+        Some((_, physical_device)) => Ok(physical_device),
+        None => Err(Error::new("No suitable physical device".to_string())),
+    }
+}
+
+fn score_physical_device(properties: &vk::PhysicalDeviceProperties) -> u32 {
+    let mut score = 0;
+
+    if properties.device_type == vk::PhysicalDeviceType::DISCRETE_GPU {
+        score += 1000;
+    }
+
+    score += properties.limits.max_image_dimension2_d;
+
+    score
 }
 
 unsafe fn check_physical_device(
@@ -336,26 +696,116 @@ unsafe fn check_physical_device(
     physical_device: vk::PhysicalDevice,
 ) -> Result<(), SuitabilityError> {
     QueueFamilyIndices::get(entry, instance, surface, physical_device)?;
+    check_physical_device_extensions(instance, physical_device)?;
+
+    let support = unsafe { SwapchainSupport::get(entry, instance, surface, physical_device) }
+        .map_err(|_| SuitabilityError("Failed to query swapchain support"))?;
+    if support.formats.is_empty() || support.present_modes.is_empty() {
+        return Err(SuitabilityError("Insufficient swapchain support"));
+    }
+
     Ok(())
 }
 
+unsafe fn check_physical_device_extensions(
+    instance: &Instance,
+    physical_device: vk::PhysicalDevice,
+) -> Result<(), SuitabilityError> {
+    let extensions = unsafe {
+        instance
+            .enumerate_device_extension_properties(physical_device)
+            .map_err(|_| SuitabilityError("Failed to enumerate device extensions"))?
+    }
+    .iter()
+    .map(|e| e.extension_name_as_c_str().unwrap().to_owned())
+    .collect::<HashSet<_>>();
+
+    if REQUIRED_DEVICE_EXTENSIONS
+        .iter()
+        .all(|e| extensions.contains(*e))
+    {
+        Ok(())
+    } else {
+        Err(SuitabilityError("Missing required device extensions"))
+    }
+}
+
+unsafe fn as_slice<'a, T>(ptr: *const T, count: u32) -> &'a [T] {
+    if ptr.is_null() {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(ptr, count as usize) }
+    }
+}
+
 extern "system" fn debug_callback(
     severity: vk::DebugUtilsMessageSeverityFlagsEXT,
     type_: vk::DebugUtilsMessageTypeFlagsEXT,
     data: *const vk::DebugUtilsMessengerCallbackDataEXT,
-    _: *mut c_void,
+    user_data: *mut c_void,
 ) -> vk::Bool32 {
+    // Validation layers can call back into us while we're already unwinding from a panic on
+    // another part of the Vulkan call stack; don't let a second panic cross the FFI boundary.
+    if std::thread::panicking() {
+        return vk::FALSE;
+    }
+
     let data = unsafe { *data };
+
+    if !user_data.is_null() {
+        let user_data = unsafe { &*(user_data as *const DebugUtilsMessengerUserData) };
+        if user_data.should_suppress(data.message_id_number) {
+            return vk::FALSE;
+        }
+    }
+
+    let message_id_name = if data.p_message_id_name.is_null() {
+        "".to_string()
+    } else {
+        unsafe { CStr::from_ptr(data.p_message_id_name) }
+            .to_string_lossy()
+            .into_owned()
+    };
     let message = unsafe { CStr::from_ptr(data.p_message) }.to_string_lossy();
 
+    let labels = unsafe { as_slice(data.p_queue_labels, data.queue_label_count) }
+        .iter()
+        .chain(unsafe { as_slice(data.p_cmd_buf_labels, data.cmd_buf_label_count) })
+        .filter(|label| !label.p_label_name.is_null())
+        .map(|label| unsafe { CStr::from_ptr(label.p_label_name) }.to_string_lossy())
+        .collect::<Vec<_>>();
+
+    let objects = unsafe { as_slice(data.p_objects, data.object_count) }
+        .iter()
+        .filter(|object| !object.p_object_name.is_null())
+        .map(|object| unsafe { CStr::from_ptr(object.p_object_name) }.to_string_lossy())
+        .collect::<Vec<_>>();
+
+    let formatted = format!(
+        "({:?}) [{}] {}{}{}",
+        type_,
+        message_id_name,
+        message,
+        if labels.is_empty() {
+            String::new()
+        } else {
+            format!(" [labels: {}]", labels.join(", "))
+        },
+        if objects.is_empty() {
+            String::new()
+        } else {
+            format!(" [objects: {}]", objects.join(", "))
+        }
+    );
+
     if severity >= vk::DebugUtilsMessageSeverityFlagsEXT::ERROR {
-        error!("({:?}) {}", type_, message);
+        error!("{}", formatted);
     } else if severity >= vk::DebugUtilsMessageSeverityFlagsEXT::WARNING {
-        warn!("({:?}) {}", type_, message);
+        warn!("{}", formatted);
     } else if severity >= vk::DebugUtilsMessageSeverityFlagsEXT::INFO {
-        debug!("({:?}) {}", type_, message);
+        debug!("{}", formatted);
     } else {
-        trace!("({:?}) {}", type_, message);
+        trace!("{}", formatted);
     }
 
     vk::FALSE